@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Wire protocol a configured provider speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    Ollama,
+    OpenaiCompatible,
+    Anthropic,
+    Gemini,
+}
+
+/// A single named provider entry, whether built-in or loaded from the user config file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderConfig {
+    pub base_url: String,
+    pub default_model: String,
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    pub kind: ProviderKind,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    providers: HashMap<String, ProviderConfig>,
+}
+
+/// Providers mansplain knows about without any user configuration.
+fn builtin_providers() -> HashMap<String, ProviderConfig> {
+    let openai_compatible = |base_url: &str, default_model: &str| ProviderConfig {
+        base_url: base_url.to_string(),
+        default_model: default_model.to_string(),
+        api_key_env: Some("MANSPLAIN_API_KEY".to_string()),
+        kind: ProviderKind::OpenaiCompatible,
+    };
+
+    let mut providers = HashMap::new();
+
+    providers.insert(
+        "ollama".to_string(),
+        ProviderConfig {
+            base_url: "http://localhost:11434".to_string(),
+            default_model: "gemma3:12b".to_string(),
+            api_key_env: None,
+            kind: ProviderKind::Ollama,
+        },
+    );
+    providers.insert(
+        "perplexity".to_string(),
+        openai_compatible("https://api.perplexity.ai", "sonar"),
+    );
+    providers.insert(
+        "openai".to_string(),
+        openai_compatible("https://api.openai.com/v1", "gpt-4o-mini"),
+    );
+    providers.insert(
+        "groq".to_string(),
+        openai_compatible(
+            "https://api.groq.com/openai/v1",
+            "llama-3.3-70b-versatile",
+        ),
+    );
+    providers.insert(
+        "mistral".to_string(),
+        openai_compatible("https://api.mistral.ai/v1", "mistral-small-latest"),
+    );
+    providers.insert(
+        "openrouter".to_string(),
+        openai_compatible("https://openrouter.ai/api/v1", "openrouter/auto"),
+    );
+    providers.insert(
+        "together".to_string(),
+        openai_compatible(
+            "https://api.together.xyz/v1",
+            "meta-llama/Llama-3.3-70B-Instruct-Turbo",
+        ),
+    );
+    providers.insert(
+        "fireworks".to_string(),
+        openai_compatible(
+            "https://api.fireworks.ai/inference/v1",
+            "accounts/fireworks/models/llama-v3p1-70b-instruct",
+        ),
+    );
+    providers.insert(
+        "deepinfra".to_string(),
+        openai_compatible(
+            "https://api.deepinfra.com/v1/openai",
+            "meta-llama/Meta-Llama-3.1-70B-Instruct",
+        ),
+    );
+    providers.insert(
+        "anthropic".to_string(),
+        ProviderConfig {
+            base_url: "https://api.anthropic.com/v1".to_string(),
+            default_model: "claude-3-5-sonnet-latest".to_string(),
+            api_key_env: Some("MANSPLAIN_API_KEY".to_string()),
+            kind: ProviderKind::Anthropic,
+        },
+    );
+    providers.insert(
+        "gemini".to_string(),
+        ProviderConfig {
+            base_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
+            default_model: "gemini-2.0-flash".to_string(),
+            api_key_env: Some("MANSPLAIN_API_KEY".to_string()),
+            kind: ProviderKind::Gemini,
+        },
+    );
+
+    providers
+}
+
+/// Path to the user config file: `MANSPLAIN_CONFIG` env var, or `mansplain/config.toml` under
+/// the platform config dir (`~/.config/mansplain/config.toml` on Linux by default).
+fn config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("MANSPLAIN_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    dirs::config_dir().map(|dir| dir.join("mansplain").join("config.toml"))
+}
+
+/// Load the provider registry: built-ins merged with (and overridden by) the user config file.
+pub fn load_providers() -> Result<HashMap<String, ProviderConfig>> {
+    let mut providers = builtin_providers();
+
+    if let Some(path) = config_path() {
+        if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file at {}", path.display()))?;
+            let parsed: ConfigFile = toml::from_str(&contents)
+                .with_context(|| format!("Failed to parse config file at {}", path.display()))?;
+            providers.extend(parsed.providers);
+        }
+    }
+
+    Ok(providers)
+}
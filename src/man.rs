@@ -0,0 +1,50 @@
+use anyhow::{Context, Result, anyhow};
+use std::process::Command;
+
+/// Shell out to `man` and return its plain-text output for `command` (optionally scoped
+/// to a section, e.g. "1", "3").
+pub async fn fetch_man_page(command: &str, section: Option<&str>) -> Result<String> {
+    let mut cmd = Command::new("man");
+
+    // Force plain output, avoid paging and control characters
+    cmd.env("MANPAGER", "cat");
+    cmd.env("LC_ALL", "C.UTF-8");
+    cmd.env("LANG", "C.UTF-8");
+
+    if let Some(sec) = section {
+        cmd.arg(sec);
+    }
+
+    cmd.arg(command);
+
+    let output = cmd
+        .output()
+        .context("Failed to execute man command. Is 'man' installed?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Failed to fetch man page: {}", stderr));
+    }
+
+    String::from_utf8(output.stdout).context("Man page output is not valid UTF-8")
+}
+
+/// Run `command --help` and return its output, for tools that ship no man page.
+/// Many CLIs write `--help` to stderr, so fall back to it if stdout is empty.
+pub async fn fetch_help_output(command: &str) -> Result<String> {
+    let output = Command::new(command)
+        .arg("--help")
+        .output()
+        .with_context(|| format!("Failed to execute '{} --help'", command))?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    if text.trim().is_empty() {
+        text = String::from_utf8_lossy(&output.stderr).into_owned();
+    }
+
+    if text.trim().is_empty() {
+        return Err(anyhow!("'{} --help' produced no output", command));
+    }
+
+    Ok(text)
+}
@@ -0,0 +1,781 @@
+use crate::retry;
+use crate::styles::Example;
+use crate::tools;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// A backend capable of turning a system prompt and a user message into a completion.
+///
+/// Implementations speak whatever wire format their API expects; the caller doesn't
+/// need to know whether that's Ollama's `/api/generate`, OpenAI's `/chat/completions`,
+/// or something else entirely.
+#[async_trait]
+pub trait Provider {
+    async fn complete(
+        &self,
+        system: &str,
+        examples: &[Example],
+        user: &str,
+        stream: bool,
+        debug: bool,
+    ) -> Result<String>;
+}
+
+// --- Ollama -----------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+struct OllamaRequest {
+    model: String,
+    prompt: String,
+    system: String,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    response: Option<String>,
+    done: bool,
+}
+
+pub struct OllamaProvider {
+    pub api_url: String,
+    pub model: String,
+    pub max_retries: u32,
+}
+
+#[async_trait]
+impl Provider for OllamaProvider {
+    async fn complete(
+        &self,
+        system: &str,
+        examples: &[Example],
+        user: &str,
+        stream: bool,
+        debug: bool,
+    ) -> Result<String> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/api/generate", self.api_url);
+
+        // Ollama has no notion of few-shot messages, so splice a compact textual
+        // version of the examples ahead of the real prompt to help smaller models
+        // lock onto the style.
+        let mut prompt = String::new();
+        for example in examples {
+            prompt.push_str(&format!(
+                "Example input:\n{}\n\nExample output:\n{}\n\n",
+                crate::styles::frame_input(example.input),
+                example.output
+            ));
+        }
+        prompt.push_str(user);
+
+        let request = OllamaRequest {
+            model: self.model.clone(),
+            prompt,
+            system: system.to_string(),
+            stream,
+        };
+
+        if debug {
+            eprintln!("[DEBUG] URL: {}", url);
+            eprintln!("[DEBUG] Payload: {}", serde_json::to_string_pretty(&request)?);
+        }
+
+        if stream {
+            // Retries only cover the initial connection; once bytes start arriving we
+            // can no longer retry without risking a doubled-up response on screen.
+            let response =
+                retry::send_with_retry(|| client.post(&url).json(&request), self.max_retries, debug)
+                    .await?;
+
+            if !response.status().is_success() {
+                return Err(anyhow!("LLM API returned error: {}", response.status()));
+            }
+
+            // True streaming: read bytes incrementally
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut full_response = String::new();
+
+            while let Some(chunk) = stream.next().await {
+                let bytes = chunk.context("Failed to read stream chunk")?;
+                buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+                // Process complete lines
+                while let Some(pos) = buffer.find('\n') {
+                    let line = buffer[..pos].to_string();
+                    buffer.drain(..=pos);
+
+                    if let Ok(obj) = serde_json::from_str::<OllamaResponse>(&line) {
+                        if let Some(text) = obj.response {
+                            print!("{}", text);
+                            full_response.push_str(&text);
+                        }
+                        if obj.done {
+                            println!();
+                            return Ok(full_response);
+                        }
+                    }
+                }
+            }
+            println!();
+            Ok(full_response)
+        } else {
+            let response =
+                retry::send_with_retry(|| client.post(&url).json(&request), self.max_retries, debug)
+                    .await?;
+
+            if !response.status().is_success() {
+                return Err(anyhow!("LLM API returned error: {}", response.status()));
+            }
+
+            let text = response
+                .text()
+                .await
+                .context("Failed to read response body")?;
+
+            // Parse non-streaming response (last JSON object)
+            let mut full_response = String::new();
+            for line in text.lines() {
+                if let Ok(chunk) = serde_json::from_str::<OllamaResponse>(line) {
+                    if let Some(resp) = chunk.response {
+                        full_response.push_str(&resp);
+                    }
+                }
+            }
+
+            Ok(full_response)
+        }
+    }
+}
+
+// --- OpenAI-compatible (OpenAI, Perplexity, Groq, Mistral, ...) -------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIMessage {
+    pub role: String,
+    // The API sends an explicit `null` (not an omitted key) for assistant messages
+    // that are pure tool calls, so this has to tolerate null rather than just missing.
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<tools::ToolCall>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl OpenAIMessage {
+    fn new(role: &str, content: String) -> Self {
+        OpenAIMessage {
+            role: role.to_string(),
+            content: Some(content),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    fn tool_result(tool_call_id: String, content: String) -> Self {
+        OpenAIMessage {
+            role: "tool".to_string(),
+            content: Some(content),
+            tool_calls: None,
+            tool_call_id: Some(tool_call_id),
+        }
+    }
+}
+
+/// system, then each example as an alternating user/assistant turn, then the real user turn.
+fn messages_with_examples(system: &str, examples: &[Example], user: &str) -> Vec<OpenAIMessage> {
+    let mut messages = vec![OpenAIMessage::new("system", system.to_string())];
+    messages.extend(example_turns(examples));
+    messages.push(OpenAIMessage::new("user", user.to_string()));
+    messages
+}
+
+/// Each example as an alternating user/assistant turn; the caller appends the real user
+/// turn. Used directly for APIs (like Anthropic's) where the system prompt is a separate
+/// top-level field rather than the first message.
+fn example_turns(examples: &[Example]) -> Vec<OpenAIMessage> {
+    let mut turns = Vec::with_capacity(examples.len() * 2);
+    for example in examples {
+        turns.push(OpenAIMessage::new("user", crate::styles::frame_input(example.input)));
+        turns.push(OpenAIMessage::new("assistant", example.output.to_string()));
+    }
+    turns
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIRequest {
+    model: String,
+    messages: Vec<OpenAIMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<tools::ToolDefinition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<&'static str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIChoice {
+    delta: Option<OpenAIDelta>,
+    message: Option<OpenAIMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIDelta {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIResponse {
+    choices: Vec<OpenAIChoice>,
+}
+
+pub struct OpenAiCompatibleProvider {
+    pub api_url: String,
+    pub model: String,
+    pub api_key: String,
+    pub max_retries: u32,
+}
+
+#[async_trait]
+impl Provider for OpenAiCompatibleProvider {
+    async fn complete(
+        &self,
+        system: &str,
+        examples: &[Example],
+        user: &str,
+        stream: bool,
+        debug: bool,
+    ) -> Result<String> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/chat/completions", self.api_url);
+
+        if stream {
+            // Tool-calling isn't implemented for the streaming path: reassembling
+            // tool_call deltas across SSE chunks isn't worth it here, so streaming
+            // requests just get a plain completion.
+            let messages = messages_with_examples(system, examples, user);
+            let request = OpenAIRequest {
+                model: self.model.clone(),
+                messages,
+                stream: true,
+                tools: None,
+                tool_choice: None,
+            };
+
+            if debug {
+                eprintln!("[DEBUG] URL: {}", url);
+                eprintln!("[DEBUG] Payload: {}", serde_json::to_string_pretty(&request)?);
+            }
+
+            let response = retry::send_with_retry(
+                || {
+                    client
+                        .post(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .header("Content-Type", "application/json")
+                        .json(&request)
+                },
+                self.max_retries,
+                debug,
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "LLM API returned error: {}\nDetails: {}",
+                    status,
+                    error_text
+                ));
+            }
+
+            // True streaming: read bytes incrementally (SSE format)
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut full_response = String::new();
+
+            while let Some(chunk) = stream.next().await {
+                let bytes = chunk.context("Failed to read stream chunk")?;
+                buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+                // Process complete lines prefixed with "data: "
+                while let Some(pos) = buffer.find('\n') {
+                    let line = buffer[..pos].trim_end().to_string();
+                    buffer.drain(..=pos);
+
+                    if !line.starts_with("data: ") {
+                        continue;
+                    }
+                    let data = line.trim_start_matches("data: ").trim();
+                    if data == "[DONE]" {
+                        println!();
+                        return Ok(full_response);
+                    }
+
+                    if let Ok(obj) = serde_json::from_str::<OpenAIResponse>(data) {
+                        if let Some(choice) = obj.choices.first() {
+                            if let Some(delta) = &choice.delta {
+                                if let Some(content) = &delta.content {
+                                    print!("{}", content);
+                                    full_response.push_str(content);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            println!();
+            return Ok(full_response);
+        }
+
+        // Non-streaming: let the model pull in related man pages (e.g. from SEE ALSO)
+        // via the fetch_man_page tool, resolving tool calls in a loop until it settles
+        // on a final answer or we hit the tool-invocation cap.
+        let mut messages = messages_with_examples(system, examples, user);
+        let mut tools_enabled = true;
+        let mut tool_calls_used = 0u32;
+
+        loop {
+            let request = OpenAIRequest {
+                model: self.model.clone(),
+                messages: messages.clone(),
+                stream: false,
+                tools: tools_enabled.then(|| vec![tools::fetch_man_page_tool()]),
+                tool_choice: tools_enabled.then_some("auto"),
+            };
+
+            if debug {
+                eprintln!("[DEBUG] URL: {}", url);
+                eprintln!("[DEBUG] Payload: {}", serde_json::to_string_pretty(&request)?);
+            }
+
+            let response = retry::send_with_retry(
+                || {
+                    client
+                        .post(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .header("Content-Type", "application/json")
+                        .json(&request)
+                },
+                self.max_retries,
+                debug,
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                // Providers that don't support function calling tend to reject the
+                // `tools` field outright with a 4xx; fall back to a plain completion
+                // once. A 429/5xx here is `send_with_retry` surfacing a persistent
+                // rate limit or server error after exhausting retries, not a tools
+                // rejection, so don't spend a whole second retry budget on it.
+                if tools_enabled && status.is_client_error() && !retry::is_retryable_status(status) {
+                    if debug {
+                        eprintln!(
+                            "[DEBUG] Provider rejected tools ({}), retrying without them",
+                            status
+                        );
+                    }
+                    tools_enabled = false;
+                    continue;
+                }
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "LLM API returned error: {}\nDetails: {}",
+                    status,
+                    error_text
+                ));
+            }
+
+            let api_response: OpenAIResponse = response
+                .json()
+                .await
+                .context("Failed to parse API response")?;
+
+            let message = api_response
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.message)
+                .ok_or_else(|| anyhow!("No response content in API response"))?;
+
+            let calls = message.tool_calls.clone().unwrap_or_default();
+            if calls.is_empty() {
+                return Ok(message.content.unwrap_or_default());
+            }
+
+            messages.push(message);
+            for call in &calls {
+                let result = tools::run_tool_call(call).await?;
+                messages.push(OpenAIMessage::tool_result(call.id.clone(), result));
+            }
+            tool_calls_used += calls.len() as u32;
+
+            if tool_calls_used >= tools::MAX_TOOL_CALLS {
+                // Stop offering the tool so the model is forced to produce a final answer.
+                tools_enabled = false;
+            }
+        }
+    }
+}
+
+// --- Anthropic Claude ---------------------------------------------------------
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const ANTHROPIC_MAX_TOKENS: u32 = 4096;
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest {
+    model: String,
+    system: String,
+    messages: Vec<OpenAIMessage>,
+    max_tokens: u32,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContentBlock {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicStreamDelta {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicStreamEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    delta: Option<AnthropicStreamDelta>,
+}
+
+pub struct AnthropicProvider {
+    pub api_url: String,
+    pub model: String,
+    pub api_key: String,
+    pub max_retries: u32,
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    async fn complete(
+        &self,
+        system: &str,
+        examples: &[Example],
+        user: &str,
+        stream: bool,
+        debug: bool,
+    ) -> Result<String> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/messages", self.api_url);
+
+        let mut messages = example_turns(examples);
+        messages.push(OpenAIMessage::new("user", user.to_string()));
+
+        let request = AnthropicRequest {
+            model: self.model.clone(),
+            system: system.to_string(),
+            messages,
+            max_tokens: ANTHROPIC_MAX_TOKENS,
+            stream,
+        };
+
+        if debug {
+            eprintln!("[DEBUG] URL: {}", url);
+            eprintln!("[DEBUG] Payload: {}", serde_json::to_string_pretty(&request)?);
+        }
+
+        let build = || {
+            client
+                .post(&url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .header("Content-Type", "application/json")
+                .json(&request)
+        };
+
+        if stream {
+            let response = retry::send_with_retry(build, self.max_retries, debug).await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "LLM API returned error: {}\nDetails: {}",
+                    status,
+                    error_text
+                ));
+            }
+
+            // True streaming: read bytes incrementally (SSE format)
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut full_response = String::new();
+
+            while let Some(chunk) = stream.next().await {
+                let bytes = chunk.context("Failed to read stream chunk")?;
+                buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+                while let Some(pos) = buffer.find('\n') {
+                    let line = buffer[..pos].trim_end().to_string();
+                    buffer.drain(..=pos);
+
+                    if !line.starts_with("data: ") {
+                        continue;
+                    }
+                    let data = line.trim_start_matches("data: ").trim();
+
+                    if let Ok(event) = serde_json::from_str::<AnthropicStreamEvent>(data) {
+                        if event.event_type == "content_block_delta" {
+                            if let Some(text) = event.delta.and_then(|d| d.text) {
+                                print!("{}", text);
+                                full_response.push_str(&text);
+                            }
+                        }
+                    }
+                }
+            }
+            println!();
+            Ok(full_response)
+        } else {
+            let response = retry::send_with_retry(build, self.max_retries, debug).await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "LLM API returned error: {}\nDetails: {}",
+                    status,
+                    error_text
+                ));
+            }
+
+            let api_response: AnthropicResponse = response
+                .json()
+                .await
+                .context("Failed to parse API response")?;
+
+            let content = api_response
+                .content
+                .into_iter()
+                .filter_map(|block| block.text)
+                .collect::<Vec<_>>()
+                .join("");
+
+            Ok(content)
+        }
+    }
+}
+
+// --- Google Gemini ------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+struct GeminiPart {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiContent {
+    role: &'static str,
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiSystemInstruction {
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    system_instruction: GeminiSystemInstruction,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiResponsePart {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiResponseContent {
+    parts: Vec<GeminiResponsePart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiCandidate {
+    content: Option<GeminiResponseContent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiResponse {
+    candidates: Vec<GeminiCandidate>,
+}
+
+pub struct GeminiProvider {
+    pub api_url: String,
+    pub model: String,
+    pub api_key: String,
+    pub max_retries: u32,
+}
+
+#[async_trait]
+impl Provider for GeminiProvider {
+    async fn complete(
+        &self,
+        system: &str,
+        examples: &[Example],
+        user: &str,
+        stream: bool,
+        debug: bool,
+    ) -> Result<String> {
+        let client = reqwest::Client::new();
+        let url = if stream {
+            // `alt=sse` gets Gemini to emit the same `data: <json>\n\n` framing as the
+            // OpenAI/Anthropic streaming endpoints, so we can reuse line-oriented parsing
+            // instead of scanning raw bytes for balanced braces (which breaks the moment
+            // the model's own output contains a brace, e.g. in a code sample).
+            format!(
+                "{}/models/{}:streamGenerateContent?alt=sse&key={}",
+                self.api_url, self.model, self.api_key
+            )
+        } else {
+            format!(
+                "{}/models/{}:generateContent?key={}",
+                self.api_url, self.model, self.api_key
+            )
+        };
+
+        let mut contents = Vec::with_capacity(examples.len() * 2 + 1);
+        for example in examples {
+            contents.push(GeminiContent {
+                role: "user",
+                parts: vec![GeminiPart {
+                    text: crate::styles::frame_input(example.input),
+                }],
+            });
+            contents.push(GeminiContent {
+                role: "model",
+                parts: vec![GeminiPart {
+                    text: example.output.to_string(),
+                }],
+            });
+        }
+        contents.push(GeminiContent {
+            role: "user",
+            parts: vec![GeminiPart {
+                text: user.to_string(),
+            }],
+        });
+
+        let request = GeminiRequest {
+            contents,
+            system_instruction: GeminiSystemInstruction {
+                parts: vec![GeminiPart {
+                    text: system.to_string(),
+                }],
+            },
+        };
+
+        if debug {
+            eprintln!("[DEBUG] URL: {}", redact_api_key(&url));
+            eprintln!("[DEBUG] Payload: {}", serde_json::to_string_pretty(&request)?);
+        }
+
+        let build = || client.post(&url).header("Content-Type", "application/json").json(&request);
+
+        if stream {
+            let response = retry::send_with_retry(build, self.max_retries, debug).await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "LLM API returned error: {}\nDetails: {}",
+                    status,
+                    error_text
+                ));
+            }
+
+            // True streaming: read bytes incrementally (SSE format, same as the
+            // OpenAI/Anthropic paths)
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut full_response = String::new();
+
+            while let Some(chunk) = stream.next().await {
+                let bytes = chunk.context("Failed to read stream chunk")?;
+                buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+                while let Some(pos) = buffer.find('\n') {
+                    let line = buffer[..pos].trim_end().to_string();
+                    buffer.drain(..=pos);
+
+                    if !line.starts_with("data: ") {
+                        continue;
+                    }
+                    let data = line.trim_start_matches("data: ").trim();
+
+                    if let Ok(obj) = serde_json::from_str::<GeminiResponse>(data) {
+                        if let Some(text) = extract_gemini_text(&obj) {
+                            print!("{}", text);
+                            full_response.push_str(&text);
+                        }
+                    }
+                }
+            }
+            println!();
+            Ok(full_response)
+        } else {
+            let response = retry::send_with_retry(build, self.max_retries, debug).await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "LLM API returned error: {}\nDetails: {}",
+                    status,
+                    error_text
+                ));
+            }
+
+            let api_response: GeminiResponse = response
+                .json()
+                .await
+                .context("Failed to parse API response")?;
+
+            extract_gemini_text(&api_response)
+                .ok_or_else(|| anyhow!("No response content in API response"))
+        }
+    }
+}
+
+fn extract_gemini_text(response: &GeminiResponse) -> Option<String> {
+    let content = response.candidates.first()?.content.as_ref()?;
+    let text = content
+        .parts
+        .iter()
+        .filter_map(|p| p.text.as_deref())
+        .collect::<Vec<_>>()
+        .join("");
+    if text.is_empty() { None } else { Some(text) }
+}
+
+/// Gemini puts the API key in the URL query string rather than a header, so it must
+/// be stripped before the URL is ever logged (e.g. in `--debug` output).
+fn redact_api_key(url: &str) -> String {
+    match url.split_once("key=") {
+        Some((prefix, _)) => format!("{}key=REDACTED", prefix),
+        None => url.to_string(),
+    }
+}
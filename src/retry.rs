@@ -0,0 +1,84 @@
+use anyhow::{Context, Result, anyhow};
+use rand::Rng;
+use std::time::Duration;
+
+/// Default cap on retry attempts for transient failures, overridable via `--max-retries`.
+pub const DEFAULT_MAX_RETRIES: u32 = 4;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(16);
+
+/// Whether an HTTP status is worth retrying (rate limited or server error).
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.as_u16() == 429 || status.is_server_error()
+}
+
+/// Exponential backoff starting at 500ms and doubling up to a 16s cap, with full jitter.
+pub fn backoff_with_jitter(attempt: u32) -> Duration {
+    let exp = INITIAL_BACKOFF.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX));
+    let capped = exp.min(MAX_BACKOFF);
+    let jittered_ms = rand::thread_rng().gen_range(0..=capped.as_millis() as u64);
+    Duration::from_millis(jittered_ms)
+}
+
+/// Parse a `Retry-After` header, which per RFC 7231 is either a number of seconds
+/// or an HTTP date. We only honor the (far more common) seconds form.
+pub fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    let seconds: u64 = value.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Send a request, retrying on HTTP 429/5xx and connection errors with exponential
+/// backoff (honoring `Retry-After` when present). `build` is called once per attempt
+/// so the request can be reconstructed from scratch. Returns the first response whose
+/// status isn't retryable (which may be a 4xx), or, once `max_retries` retries are
+/// exhausted, the last 429/5xx response or connection error.
+///
+/// Only meant to guard the initial connection: callers that stream a response body
+/// must not call this again once bytes have started arriving.
+pub async fn send_with_retry<F>(
+    build: F,
+    max_retries: u32,
+    debug: bool,
+) -> Result<reqwest::Response>
+where
+    F: Fn() -> reqwest::RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        match build().send().await {
+            Ok(response) if is_retryable_status(response.status()) && attempt < max_retries => {
+                let wait = parse_retry_after(response.headers())
+                    .unwrap_or_else(|| backoff_with_jitter(attempt));
+                if debug {
+                    eprintln!(
+                        "[DEBUG] Got {}, retrying in {:?} (attempt {}/{})",
+                        response.status(),
+                        wait,
+                        attempt + 1,
+                        max_retries
+                    );
+                }
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            Ok(response) => return Ok(response),
+            Err(err) if (err.is_connect() || err.is_timeout()) && attempt < max_retries => {
+                let wait = backoff_with_jitter(attempt);
+                if debug {
+                    eprintln!(
+                        "[DEBUG] Connection error ({}), retrying in {:?} (attempt {}/{})",
+                        err,
+                        wait,
+                        attempt + 1,
+                        max_retries
+                    );
+                }
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(anyhow!(err)).context("Failed to connect to LLM API"),
+        }
+    }
+}
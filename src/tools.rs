@@ -0,0 +1,103 @@
+use crate::man::fetch_man_page;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Cap on tool invocations per completion: once this many calls have run, the tool is
+/// no longer offered, so a chatty model can't chase SEE ALSO references forever. All
+/// calls the model requests in one response are run, so the last round can take the
+/// total past this number.
+pub const MAX_TOOL_CALLS: u32 = 3;
+
+/// Truncate fetched pages to this many bytes before handing them back to the model,
+/// so a long nested man page can't blow out the request body.
+const MAN_PAGE_BYTE_BUDGET: usize = 4000;
+
+#[derive(Debug, Serialize)]
+pub struct ToolDefinition {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub function: ToolFunctionDef,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolFunctionDef {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: ToolCallFunction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolCallFunction {
+    pub name: String,
+    pub arguments: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FetchManPageArgs {
+    command: String,
+    section: Option<String>,
+}
+
+/// The one tool we expose: pulling in a related man page named in a SEE ALSO section.
+pub fn fetch_man_page_tool() -> ToolDefinition {
+    ToolDefinition {
+        kind: "function",
+        function: ToolFunctionDef {
+            name: "fetch_man_page",
+            description: "Fetch the man page for a related command, e.g. one named in a SEE ALSO section.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The command to fetch the man page for, e.g. \"grep\"",
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "Optional man section to disambiguate, e.g. \"3\" for printf(3)",
+                    },
+                },
+                "required": ["command"],
+            }),
+        },
+    }
+}
+
+/// Run a single `fetch_man_page` tool call and return the (possibly truncated) page
+/// text to feed back to the model as the `tool` message content.
+pub async fn run_tool_call(call: &ToolCall) -> Result<String> {
+    if call.function.name != "fetch_man_page" {
+        return Ok(format!("Unknown tool '{}'", call.function.name));
+    }
+
+    let args: FetchManPageArgs = match serde_json::from_str(&call.function.arguments) {
+        Ok(args) => args,
+        Err(err) => return Ok(format!("Invalid arguments for fetch_man_page: {}", err)),
+    };
+
+    let page = match fetch_man_page(&args.command, args.section.as_deref()).await {
+        Ok(page) => page,
+        Err(err) => return Ok(format!("Failed to fetch man page for '{}': {}", args.command, err)),
+    };
+
+    if page.len() > MAN_PAGE_BYTE_BUDGET {
+        let mut end = MAN_PAGE_BYTE_BUDGET;
+        while !page.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut truncated = page[..end].to_string();
+        truncated.push_str("\n[... truncated ...]");
+        Ok(truncated)
+    } else {
+        Ok(page)
+    }
+}